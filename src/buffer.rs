@@ -1,8 +1,10 @@
 pub mod read_buf {
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use core::ops::Index;
     use core::slice::SliceIndex;
-    use std::convert::TryInto;
-    use std::io::{self, Read};
-    use std::ops::Index;
+
+    use crate::io_nostd::{self as io, Read};
 
     /// Simple buffer to cache read data
     #[derive(Debug)]
@@ -35,13 +37,10 @@ pub mod read_buf {
             Ok(())
         }
 
-        /// Get 4 bytes from buffer as u32
-        pub fn get_u32(&self, index: usize) -> u32 {
-            let offset = self.n + index + std::mem::size_of::<u32>();
-            assert!(offset <= self.end);
-
-            let (int_bytes, _) = self.buf.split_at(offset);
-            u32::from_le_bytes(int_bytes.try_into().unwrap())
+        /// Append already-read bytes, as if they had just come from `extend_read`
+        pub fn extend_from_slice(&mut self, data: &[u8]) {
+            self.buf[self.end..self.end + data.len()].copy_from_slice(data);
+            self.end += data.len();
         }
 
         /// Drop read data and possible move rest to beginnig of buffer
@@ -70,7 +69,7 @@ pub mod read_buf {
             }
             let v = self.buf[self.n];
             self.n += 1;
-            return Some(v);
+            Some(v)
         }
     }
 
@@ -85,8 +84,12 @@ pub mod read_buf {
 } // mod read_buf
 
 pub mod write_buf {
-    use std::cmp;
-    use std::io::{self, Write};
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use core::cmp;
+
+    use crate::io_nostd::{self as io, Write};
+    use crate::xxhash::Xxh32;
 
     /// Buffer writes data to underlying writer and keep last chunk in internal storage
     #[derive(Debug)]
@@ -95,6 +98,7 @@ pub mod write_buf {
         buf: Box<[u8]>,
         end: usize,
         total_written: usize,
+        content_hash: Option<Xxh32>,
     }
 
     impl<W: Write> Lz4WriteBuf<W> {
@@ -102,13 +106,53 @@ pub mod write_buf {
         pub fn with_capacity(inner: W, cap: usize) -> Self {
             let cap_round = cap.next_power_of_two();
             Lz4WriteBuf {
-                inner: inner,
+                inner,
                 buf: vec![0u8; cap_round].into_boxed_slice(),
                 end: 0,
                 total_written: 0,
+                content_hash: None,
             }
         }
 
+        /// Start accumulating an XXH32 checksum over all bytes written from now on
+        pub fn enable_content_checksum(&mut self) {
+            self.content_hash = Some(Xxh32::new(0));
+        }
+
+        /// Stop accumulating a content checksum, discarding any progress so far
+        pub fn disable_content_checksum(&mut self) {
+            self.content_hash = None;
+        }
+
+        /// Reference to the underlying writer
+        pub fn get_ref(&self) -> &W {
+            &self.inner
+        }
+
+        /// Mutable reference to the underlying writer
+        pub fn get_mut(&mut self) -> &mut W {
+            &mut self.inner
+        }
+
+        /// Total number of bytes written through this buffer so far
+        pub fn total_written(&self) -> usize {
+            self.total_written
+        }
+
+        /// Checksum of all bytes written so far, if `enable_content_checksum` was called
+        pub fn content_checksum(&self) -> Option<u32> {
+            self.content_hash.as_ref().map(|h| h.digest())
+        }
+
+        /// Seed the ring buffer with dictionary content so that back-references
+        /// in data written afterwards can reach into it, without forwarding it to `inner`
+        pub fn preload(&mut self, dict: &[u8]) {
+            let take = cmp::min(dict.len(), self.buf.len());
+            let start = dict.len() - take;
+            self.buf[..take].copy_from_slice(&dict[start..]);
+            self.end = take & (self.buf.len() - 1);
+        }
+
         /// Copy `amt` bytes from buffer  with `offset` from end to underlying writer
         pub fn copy_from_offset(&mut self, offset: usize, mut amt: usize) -> io::Result<()> {
             assert!(offset < self.buf.len());
@@ -126,7 +170,7 @@ pub mod write_buf {
                 idx = (idx + offset) & (self.buf.len() - 1);
                 amt -= offset;
             }
-            return self.copy_non_overlap(idx, amt);
+            self.copy_non_overlap(idx, amt)
         }
 
         fn copy_non_overlap(&mut self, index: usize, amt: usize) -> io::Result<()> {
@@ -139,6 +183,9 @@ pub mod write_buf {
             );
 
             self.total_written += n;
+            if let Some(hash) = self.content_hash.as_mut() {
+                hash.update(&self.buf[index..index + n]);
+            }
             self.inner.write_all(&self.buf[index..index + n])?;
             self.buf.copy_within(index..index + n, self.end);
             self.end = (self.end + n) & (self.buf.len() - 1);
@@ -146,13 +193,21 @@ pub mod write_buf {
             if n < amt {
                 return self.copy_non_overlap((index + n) & (self.buf.len() - 1), amt - n);
             }
-            return Ok(());
+            Ok(())
         }
     }
 
     impl<W: Write> Write for Lz4WriteBuf<W> {
         fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
             let amt = self.inner.write(buf)?;
+            if let Some(hash) = self.content_hash.as_mut() {
+                hash.update(&buf[..amt]);
+            }
+            // A write larger than the window takes the early return below,
+            // which skips the `total_written` update at the end
+            if amt > self.buf.len() {
+                self.total_written += amt;
+            }
             if amt > self.buf.len() {
                 let n = amt - self.buf.len();
                 self.buf.copy_from_slice(&buf[n..]);
@@ -165,7 +220,7 @@ pub mod write_buf {
             self.buf[..amt - n].copy_from_slice(&buf[n..amt]);
             self.end = (self.end + amt) & (self.buf.len() - 1);
             self.total_written += amt;
-            return Ok(amt);
+            Ok(amt)
         }
 
         fn flush(&mut self) -> io::Result<()> {
@@ -173,7 +228,7 @@ pub mod write_buf {
         }
     }
 
-    #[cfg(test)]
+    #[cfg(all(test, feature = "std"))]
     mod tests {
         use super::*;
 
@@ -185,13 +240,13 @@ pub mod write_buf {
         impl TestWrite {
             fn new(data: Vec<u8>, max_write_size: usize) -> Self {
                 TestWrite {
-                    data: data,
-                    max_write_size: max_write_size,
+                    data,
+                    max_write_size,
                 }
             }
 
             fn completed(&self) -> bool {
-                self.data.len() == 0
+                self.data.is_empty()
             }
         }
 
@@ -288,5 +343,19 @@ pub mod write_buf {
 
             assert!(tw.completed());
         }
+
+        #[test]
+        fn test_preload() {
+            let dict = b"0123456789".to_vec();
+            let mut tw = TestWrite::new(b"012".to_vec(), 100);
+            let mut w = Lz4WriteBuf::with_capacity(&mut tw, 16);
+
+            w.preload(&dict);
+            assert_eq!(w.end, dict.len());
+
+            let res = w.copy_from_offset(dict.len(), 3);
+            assert!(res.is_ok());
+            assert!(tw.completed());
+        }
     }
 } // mod write_buf
@@ -1,10 +1,13 @@
-use std::error::Error;
-use std::fmt;
-use std::io::{self, Read, Write};
-use std::result::Result;
-use std::u32;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+use core::mem;
+use core::result::Result;
 
 use crate::buffer::{read_buf::Lz4ReadBuf, write_buf::Lz4WriteBuf};
+use crate::io_nostd::{self as io, Read, Write};
+use crate::xxhash::xxh32;
 
 #[derive(Debug)]
 pub enum DecodeError {
@@ -24,6 +27,10 @@ pub enum DecodeError {
     CorruptedData,
     /// All data decopressed but reader contains unrecognized data at end
     UnknownDataAtEnd,
+    /// XXH32 checksum of content or block does not match the one stored in the stream
+    ChecksumMismatch,
+    /// Decoded byte count does not match the frame header's content size field
+    ContentSizeMismatch { expected: u64, actual: u64 },
 }
 
 use DecodeError::*;
@@ -32,40 +39,58 @@ type DecodeResult<T> = Result<T, DecodeError>;
 
 impl fmt::Display for DecodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", "Decode Error")
+        write!(f, "Decode Error")
     }
 }
 
 impl Error for DecodeError {}
 
+impl From<DecodeError> for io::Error {
+    fn from(e: DecodeError) -> Self {
+        match e {
+            DecodeError::WriteIoError(e) => e,
+            DecodeError::ReadIoError(e) => e,
+            _ => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
 /// Decoder for LZ4 compressed data
 #[derive(Debug)]
 pub struct LzDecoder {
     input_buffer: Lz4ReadBuf,
+    dictionary: Option<Vec<u8>>,
+    declared_content_size: Option<u64>,
+    declared_dict_id: Option<u32>,
 }
 
 #[derive(Debug)]
 struct FrameHeaderInfo {
-    block_indep_flag: bool,
     block_checksum_flag: bool,
-    content_size_flag: bool,
+    content_size: Option<u64>,
     content_checksum_flag: bool,
-    dict_id_flag: bool,
-    block_max_size: u8,
-    header_size: usize,
 }
 
 #[inline]
 fn is_bit_set(n: u8, i: u8) -> bool {
-    return n & (1 << i) != 0;
+    n & (1 << i) != 0
+}
+
+impl Default for LzDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl LzDecoder {
     const INPUT_BUFFER_SIZE: usize = 1 << 22;
     const WINDOW_SIZE: usize = 1 << 16;
     const FRAME_MAGIC: u32 = 0x184D2204;
-    const U32_LEN: usize = std::mem::size_of::<u32>();
-    const U16_LEN: usize = std::mem::size_of::<u16>();
+    const SKIPPABLE_MAGIC_LO: u32 = 0x184D2A50;
+    const SKIPPABLE_MAGIC_HI: u32 = 0x184D2A5F;
+    const U64_LEN: usize = mem::size_of::<u64>();
+    const U32_LEN: usize = mem::size_of::<u32>();
+    const U16_LEN: usize = mem::size_of::<u16>();
 
     const BASE_MATCH_LEN: usize = 4;
 
@@ -73,10 +98,43 @@ impl LzDecoder {
     pub fn new() -> Self {
         LzDecoder {
             input_buffer: Lz4ReadBuf::with_capacity(Self::INPUT_BUFFER_SIZE),
+            dictionary: None,
+            declared_content_size: None,
+            declared_dict_id: None,
+        }
+    }
+
+    /// Create new decoder that seeds its decode window with `dict`, so that
+    /// back-references in the first block can reach into its content
+    pub fn with_dictionary(dict: &[u8]) -> Self {
+        LzDecoder {
+            input_buffer: Lz4ReadBuf::with_capacity(Self::INPUT_BUFFER_SIZE),
+            dictionary: Some(dict.to_vec()),
+            declared_content_size: None,
+            declared_dict_id: None,
         }
     }
 
-    /// Read compressed data from `input` and write decopressed  to `output`
+    /// Content size declared by the most recently parsed frame header, if
+    /// the frame carries one. The header is parsed by `decode`, which also
+    /// decodes the whole stream, so this is only set once `decode` has run;
+    /// for a stream of several frames it holds the last frame's value.
+    pub fn required_bytes(&self) -> Option<u64> {
+        self.declared_content_size
+    }
+
+    /// DictID declared by the most recently parsed frame header, if the
+    /// frame carries one; lets callers confirm the frame was encoded
+    /// against the dictionary they expect.
+    pub fn dict_id(&self) -> Option<u32> {
+        self.declared_dict_id
+    }
+
+    /// Read compressed data from `input` and write decopressed  to `output`.
+    ///
+    /// `input` may hold several concatenated LZ4 frames, optionally
+    /// interleaved with skippable frames; all of them are decoded in turn
+    /// into `output` until `input` is exhausted.
     pub fn decode<R, W>(&mut self, input: &mut R, output: &mut W) -> DecodeResult<()>
     where
         R: Read,
@@ -84,62 +142,174 @@ impl LzDecoder {
     {
         let mut output = Lz4WriteBuf::with_capacity(output, Self::WINDOW_SIZE);
 
-        let frame_header = self.parse_header(input)?;
-
-        let FrameHeaderInfo { dict_id_flag, .. } = frame_header;
-
-        if dict_id_flag {
-            return Err(DecodeError::UnsuppotedFeature("DictID".to_string()));
+        if let Some(dict) = &self.dictionary {
+            output.preload(dict);
         }
 
-        loop {
-            self.input_buffer.compact();
+        let mut any_frame_decoded = false;
 
-            let bs_data = self.read_u32(input)?;
+        while let Some(magic) = self.peek_magic(input)? {
+            if Self::is_skippable_magic(magic) {
+                self.skip_frame(input)?;
+                any_frame_decoded = true;
+                continue;
+            }
 
-            let mask = 1 << 31;
-            let is_raw = bs_data & mask != 0;
+            if magic != Self::FRAME_MAGIC {
+                if any_frame_decoded {
+                    return Err(DecodeError::UnknownDataAtEnd);
+                }
+                return Err(DecodeError::WrongMagic);
+            }
 
-            let block_size = (bs_data & (mask - 1)) as usize;
+            let frame_header = self.parse_header(input, magic)?;
 
-            if bs_data == 0 {
-                break;
+            if frame_header.content_checksum_flag {
+                output.enable_content_checksum();
+            } else {
+                output.disable_content_checksum();
             }
 
-            if block_size >= self.input_buffer.capacity() {
-                return Err(DecodeError::InvalidBlockSize(block_size));
+            let bytes_before = output.total_written();
+
+            while let Some(is_raw) = self.next_block(input, &frame_header)? {
+                if is_raw {
+                    let n = self.input_buffer.len();
+                    output
+                        .write_all(&self.input_buffer[..n])
+                        .map_err(WriteIoError)?;
+                    self.input_buffer.consume(n);
+                    continue;
+                }
+
+                loop {
+                    let block_completed = self.process_sequence(&mut output)?;
+                    if block_completed {
+                        break;
+                    }
+                }
             }
 
-            self.input_buffer
-                .extend_read(input, block_size)
-                .map_err(ReadIoError)?;
+            self.check_content_checksum(input, &frame_header, output.content_checksum())?;
+            Self::check_content_size(&frame_header, output.total_written() - bytes_before)?;
+            any_frame_decoded = true;
+        }
 
-            if is_raw {
-                let n = self.input_buffer.len();
-                output
-                    .write_all(&self.input_buffer[..n])
-                    .map_err(WriteIoError)?;
-                self.input_buffer.consume(n);
-                continue;
+        Ok(())
+    }
+
+    /// Read up to the next 4 bytes of `input` without consuming them from
+    /// `input_buffer`, so a following `parse_header` call can pick them back
+    /// up as the frame magic. Returns `None` at a clean end of stream.
+    fn peek_magic<R: Read>(&mut self, input: &mut R) -> DecodeResult<Option<u32>> {
+        self.input_buffer.compact();
+
+        let mut magic_buf = [0u8; Self::U32_LEN];
+        let mut filled = 0;
+        while filled < magic_buf.len() {
+            let n = input.read(&mut magic_buf[filled..]).map_err(ReadIoError)?;
+            if n == 0 {
+                break;
             }
+            filled += n;
+        }
 
-            loop {
-                let block_completed = self.process_sequence(&mut output)?;
-                if block_completed {
-                    break;
-                }
+        if filled == 0 {
+            return Ok(None);
+        }
+        if filled < magic_buf.len() {
+            return Err(DecodeError::CorruptedData);
+        }
+
+        self.input_buffer.extend_from_slice(&magic_buf);
+        Ok(Some(u32::from_le_bytes(magic_buf)))
+    }
+
+    #[inline]
+    fn is_skippable_magic(magic: u32) -> bool {
+        (Self::SKIPPABLE_MAGIC_LO..=Self::SKIPPABLE_MAGIC_HI).contains(&magic)
+    }
+
+    /// Discard a skippable frame: its magic is already sitting unconsumed at
+    /// the front of `input_buffer` (via `peek_magic`); the 4-byte size field
+    /// and payload are read directly from `input`.
+    fn skip_frame<R: Read>(&mut self, input: &mut R) -> DecodeResult<()> {
+        self.input_buffer.consume(Self::U32_LEN);
+        self.input_buffer.compact();
+
+        let mut remaining = self.read_u32(input)? as usize;
+        let mut scratch = [0u8; 4096];
+        while remaining > 0 {
+            let n = core::cmp::min(remaining, scratch.len());
+            input.read_exact(&mut scratch[..n]).map_err(ReadIoError)?;
+            remaining -= n;
+        }
+        Ok(())
+    }
+
+    /// Read the next block-size field and, unless it is the end mark, fill
+    /// `input_buffer` with the block's on-wire bytes and validate its checksum.
+    /// Returns `None` at the end mark, otherwise `Some(is_raw)`.
+    fn next_block<R: Read>(
+        &mut self,
+        input: &mut R,
+        frame_header: &FrameHeaderInfo,
+    ) -> DecodeResult<Option<bool>> {
+        self.input_buffer.compact();
+
+        let bs_data = self.read_u32(input)?;
+
+        if bs_data == 0 {
+            return Ok(None);
+        }
+
+        let mask = 1 << 31;
+        let is_raw = bs_data & mask != 0;
+        let block_size = (bs_data & (mask - 1)) as usize;
+
+        if block_size >= self.input_buffer.capacity() {
+            return Err(DecodeError::InvalidBlockSize(block_size));
+        }
+
+        self.input_buffer
+            .extend_read(input, block_size)
+            .map_err(ReadIoError)?;
+
+        if frame_header.block_checksum_flag {
+            let expected = self.read_u32(input)?;
+            let actual = xxh32(&self.input_buffer[..block_size], 0);
+            if actual != expected {
+                return Err(DecodeError::ChecksumMismatch);
             }
         }
 
+        Ok(Some(is_raw))
+    }
+
+    /// Validate the content checksum (if present) following a frame's end mark
+    fn check_content_checksum<R: Read>(
+        &self,
+        input: &mut R,
+        frame_header: &FrameHeaderInfo,
+        actual_content_checksum: Option<u32>,
+    ) -> DecodeResult<()> {
         if frame_header.content_checksum_flag {
-            // TODO do not ignore content checksum
-            let _ = self.read_u32(input)?;
+            let expected = self.read_u32(input)?;
+            if actual_content_checksum.unwrap() != expected {
+                return Err(DecodeError::ChecksumMismatch);
+            }
         }
+        Ok(())
+    }
 
-        let mut dummy_buf = [0u8; 4];
-        let n = input.read(&mut dummy_buf).map_err(ReadIoError)?;
-        if n != 0 {
-            return Err(UnknownDataAtEnd);
+    /// Validate the frame header's content size field (if present) against
+    /// the number of bytes actually decoded for that frame
+    fn check_content_size(frame_header: &FrameHeaderInfo, actual: usize) -> DecodeResult<()> {
+        if let Some(expected) = frame_header.content_size {
+            let actual = actual as u64;
+            if actual != expected {
+                return Err(DecodeError::ContentSizeMismatch { expected, actual });
+            }
         }
         Ok(())
     }
@@ -205,22 +375,26 @@ impl LzDecoder {
                 break;
             }
         }
-        return Ok(n);
+        Ok(n)
     }
 
-    fn parse_header<R: Read>(&mut self, input: &mut R) -> DecodeResult<FrameHeaderInfo> {
-        let min_header_size = Self::U32_LEN + 3;
-
-        self.input_buffer
-            .extend_read(input, min_header_size)
-            .map_err(ReadIoError)?;
-
-        let frame_magic = self.input_buffer.get_u32(0);
-
+    /// Parse a frame header, given its magic number (already peeked via
+    /// `peek_magic` and sitting at the front of `input_buffer`)
+    fn parse_header<R: Read>(
+        &mut self,
+        input: &mut R,
+        frame_magic: u32,
+    ) -> DecodeResult<FrameHeaderInfo> {
         if frame_magic != Self::FRAME_MAGIC {
             return Err(DecodeError::WrongMagic);
         }
 
+        let mut header_size = Self::U32_LEN + 2;
+
+        self.input_buffer
+            .extend_read(input, header_size - Self::U32_LEN)
+            .map_err(ReadIoError)?;
+
         /*
         |  BitNb  |  7-6  |   5   |    4     |  3   |    2     |    1     |   0  |
         | ------- |-------|-------|----------|------|----------|----------|------|
@@ -231,51 +405,455 @@ impl LzDecoder {
             return Err(DecodeError::WrongVersion);
         }
 
-        let mut header_size = min_header_size;
-
         let content_size_flag = is_bit_set(flg_byte, 3);
+        let mut content_size = None;
         if content_size_flag {
-            let content_size_num_size = 4;
-            // TODO: don't skip content size
+            let content_size_num_size = Self::U64_LEN;
             self.input_buffer
                 .extend_read(input, content_size_num_size)
                 .map_err(ReadIoError)?;
+            let c = &self.input_buffer[header_size..header_size + content_size_num_size];
+            content_size = Some(u64::from_le_bytes([
+                c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7],
+            ]));
             header_size += content_size_num_size;
         }
 
         let dict_id_flag = is_bit_set(flg_byte, 0);
+        let mut dict_id = None;
         if dict_id_flag {
             let dict_size = 4;
-            // TODO: don't skip dict id
             self.input_buffer
                 .extend_read(input, dict_size)
                 .map_err(ReadIoError)?;
+            let d = &self.input_buffer[header_size..header_size + dict_size];
+            dict_id = Some(u32::from_le_bytes([d[0], d[1], d[2], d[3]]));
             header_size += dict_size;
         }
 
-        /*
-        |  BitNb  |     7    |     6-5-4     |  3-2-1-0 |
-        | ------- | -------- | ------------- | -------- |
-        |FieldName|*Reserved*| Block MaxSize |*Reserved*|
-        */
-        let bd_byte = self.input_buffer[Self::U32_LEN + 1];
-
+        self.input_buffer
+            .extend_read(input, 1)
+            .map_err(ReadIoError)?;
         // TODO: check header checksum
         let _ = self.input_buffer[header_size];
+        header_size += 1;
 
         self.input_buffer.consume(header_size);
         self.input_buffer.compact();
 
+        self.declared_content_size = content_size;
+        self.declared_dict_id = dict_id;
+
         let header_info = FrameHeaderInfo {
-            block_indep_flag: is_bit_set(flg_byte, 5),
             block_checksum_flag: is_bit_set(flg_byte, 4),
-            content_size_flag: content_size_flag,
+            content_size,
             content_checksum_flag: is_bit_set(flg_byte, 2),
-            dict_id_flag: dict_id_flag,
-            block_max_size: (bd_byte & 0b01110000) >> 4,
-            header_size: header_size,
         };
 
-        return Ok(header_info);
+        Ok(header_info)
+    }
+}
+
+/// Sink that keeps decoded bytes in memory until a `Read` caller drains them
+#[derive(Debug)]
+struct QueueSink {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl QueueSink {
+    fn new() -> Self {
+        QueueSink {
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    fn drain_into(&mut self, out: &mut [u8]) -> usize {
+        let n = core::cmp::min(out.len(), self.len());
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+        }
+        n
+    }
+}
+
+impl Write for QueueSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+enum ReaderState {
+    Header,
+    BlockHeader,
+    InBlock,
+    Trailer,
+    Eof,
+}
+
+/// Adapts `LzDecoder` to `Read`, decoding just enough of the frame on each
+/// call to fill the caller's buffer instead of draining the whole stream at once
+pub struct LzDecodeReader<R> {
+    input: R,
+    decoder: LzDecoder,
+    output: Lz4WriteBuf<QueueSink>,
+    frame_header: Option<FrameHeaderInfo>,
+    frame_bytes_before: usize,
+    state: ReaderState,
+    any_frame_decoded: bool,
+}
+
+impl<R: Read> LzDecodeReader<R> {
+    /// Wrap `input` in a streaming LZ4 decoder
+    pub fn new(input: R) -> Self {
+        LzDecodeReader {
+            input,
+            decoder: LzDecoder::new(),
+            output: Lz4WriteBuf::with_capacity(QueueSink::new(), LzDecoder::WINDOW_SIZE),
+            frame_header: None,
+            frame_bytes_before: 0,
+            state: ReaderState::Header,
+            any_frame_decoded: false,
+        }
+    }
+
+    /// Content size declared by the frame currently being decoded, if any;
+    /// only populated once the header has been parsed (i.e. after the first
+    /// successful `read`)
+    pub fn required_bytes(&self) -> Option<u64> {
+        self.decoder.required_bytes()
+    }
+
+    /// DictID declared by the frame currently being decoded, if any; only
+    /// populated once the header has been parsed (i.e. after the first
+    /// successful `read`)
+    pub fn dict_id(&self) -> Option<u32> {
+        self.decoder.dict_id()
+    }
+
+    /// Advance the decoder by one resumable unit of work: a header, a block's
+    /// worth of book-keeping, a single sequence, or the frame trailer
+    fn step(&mut self) -> DecodeResult<()> {
+        match self.state {
+            ReaderState::Header => match self.decoder.peek_magic(&mut self.input)? {
+                None => self.state = ReaderState::Eof,
+                Some(magic) if LzDecoder::is_skippable_magic(magic) => {
+                    self.decoder.skip_frame(&mut self.input)?;
+                    self.any_frame_decoded = true;
+                }
+                Some(magic) if magic != LzDecoder::FRAME_MAGIC => {
+                    if self.any_frame_decoded {
+                        return Err(DecodeError::UnknownDataAtEnd);
+                    }
+                    return Err(DecodeError::WrongMagic);
+                }
+                Some(magic) => {
+                    let frame_header = self.decoder.parse_header(&mut self.input, magic)?;
+                    if frame_header.content_checksum_flag {
+                        self.output.enable_content_checksum();
+                    } else {
+                        self.output.disable_content_checksum();
+                    }
+                    self.frame_header = Some(frame_header);
+                    self.frame_bytes_before = self.output.total_written();
+                    self.state = ReaderState::BlockHeader;
+                }
+            },
+            ReaderState::BlockHeader => {
+                let frame_header = self.frame_header.as_ref().unwrap();
+                match self.decoder.next_block(&mut self.input, frame_header)? {
+                    None => self.state = ReaderState::Trailer,
+                    Some(true) => {
+                        let n = self.decoder.input_buffer.len();
+                        self.output
+                            .write_all(&self.decoder.input_buffer[..n])
+                            .map_err(WriteIoError)?;
+                        self.decoder.input_buffer.consume(n);
+                    }
+                    Some(false) => self.state = ReaderState::InBlock,
+                }
+            }
+            ReaderState::InBlock => {
+                if self.decoder.process_sequence(&mut self.output)? {
+                    self.state = ReaderState::BlockHeader;
+                }
+            }
+            ReaderState::Trailer => {
+                let frame_header = self.frame_header.take().unwrap();
+                self.decoder.check_content_checksum(
+                    &mut self.input,
+                    &frame_header,
+                    self.output.content_checksum(),
+                )?;
+                LzDecoder::check_content_size(
+                    &frame_header,
+                    self.output.total_written() - self.frame_bytes_before,
+                )?;
+                self.any_frame_decoded = true;
+                self.state = ReaderState::Header;
+            }
+            ReaderState::Eof => {}
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for LzDecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.output.get_ref().len() < buf.len() && !matches!(self.state, ReaderState::Eof) {
+            self.step()?;
+        }
+        Ok(self.output.get_mut().drain_into(buf))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_with_dictionary() {
+        let dict = b"Hello, World!".to_vec();
+
+        // one LZ4-compressed block: a match into the dictionary followed
+        // by a few literals to close out the block
+        let block: Vec<u8> = vec![
+            0x01, 13, 0, // lit_len=0, match_len=5, offset=13 -> copies "Hello" from dict
+            0x50, b' ', b'W', b'r', b'l', b'd', // lit_len=5, no match -> closes the block
+        ];
+
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&LzDecoder::FRAME_MAGIC.to_le_bytes());
+        frame.extend_from_slice(&[0x60, 0x40, 0]); // FLG, BD, HC
+        frame.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&block);
+        frame.extend_from_slice(&0u32.to_le_bytes()); // end mark
+
+        let mut decoded = Vec::new();
+        LzDecoder::with_dictionary(&dict)
+            .decode(&mut &frame[..], &mut decoded)
+            .unwrap();
+
+        assert_eq!(decoded, b"Hello Wrld");
+    }
+
+    #[test]
+    fn test_decode_dict_id() {
+        let dict_id: u32 = 0xAABBCCDD;
+
+        // one LZ4-compressed block: a single literals-only sequence closing the block
+        let block: Vec<u8> = vec![0x50, b'H', b'e', b'l', b'l', b'o'];
+
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&LzDecoder::FRAME_MAGIC.to_le_bytes());
+        frame.extend_from_slice(&[0x69, 0x40]); // FLG (block indep + content size + DictID), BD
+        frame.extend_from_slice(&5u64.to_le_bytes()); // content size
+        frame.extend_from_slice(&dict_id.to_le_bytes());
+        frame.push(0); // HC
+        frame.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&block);
+        frame.extend_from_slice(&0u32.to_le_bytes()); // end mark
+
+        let mut decoder = LzDecoder::new();
+        let mut decoded = Vec::new();
+        decoder.decode(&mut &frame[..], &mut decoded).unwrap();
+
+        assert_eq!(decoded, b"Hello");
+        assert_eq!(decoder.required_bytes(), Some(5));
+        assert_eq!(decoder.dict_id(), Some(dict_id));
+    }
+
+    #[test]
+    fn test_decode_reader_small_buf() {
+        let data: Vec<u8> = (0..10_000).map(|x| (x % 37) as u8).collect();
+
+        let mut encoded = Vec::new();
+        crate::encoder::LzEncoder::new()
+            .encode(&mut &data[..], &mut encoded)
+            .unwrap();
+
+        let mut reader = LzDecodeReader::new(&encoded[..]);
+        let mut decoded = Vec::new();
+        let mut chunk = [0u8; 7];
+        loop {
+            let n = reader.read(&mut chunk).unwrap();
+            if n == 0 {
+                break;
+            }
+            decoded.extend_from_slice(&chunk[..n]);
+        }
+
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decode_concatenated_frames() {
+        let a: Vec<u8> = (0..5_000).map(|x| (x % 17) as u8).collect();
+        let b: Vec<u8> = (0..3_000).map(|x| (x % 29) as u8).collect();
+
+        let mut stream = Vec::new();
+        crate::encoder::LzEncoder::new()
+            .encode(&mut &a[..], &mut stream)
+            .unwrap();
+        crate::encoder::LzEncoder::new()
+            .encode(&mut &b[..], &mut stream)
+            .unwrap();
+
+        let mut decoded = Vec::new();
+        LzDecoder::new()
+            .decode(&mut &stream[..], &mut decoded)
+            .unwrap();
+
+        let mut expected = a;
+        expected.extend_from_slice(&b);
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn test_decode_skippable_frame() {
+        let data = b"hello hello hello".to_vec();
+
+        let mut stream = Vec::new();
+        stream.extend_from_slice(&0x184D2A50u32.to_le_bytes());
+        stream.extend_from_slice(&4u32.to_le_bytes());
+        stream.extend_from_slice(b"skip");
+
+        crate::encoder::LzEncoder::new()
+            .encode(&mut &data[..], &mut stream)
+            .unwrap();
+
+        let mut decoded = Vec::new();
+        LzDecoder::new()
+            .decode(&mut &stream[..], &mut decoded)
+            .unwrap();
+
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decode_garbage_at_start_is_wrong_magic() {
+        let stream = [0xDE, 0xAD, 0xBE, 0xEF];
+
+        let mut decoded = Vec::new();
+        let err = LzDecoder::new()
+            .decode(&mut &stream[..], &mut decoded)
+            .unwrap_err();
+
+        assert!(matches!(err, DecodeError::WrongMagic));
+    }
+
+    #[test]
+    fn test_decode_trailing_garbage_after_frame_is_unknown_data_at_end() {
+        let data = b"hello hello hello".to_vec();
+
+        let mut stream = Vec::new();
+        crate::encoder::LzEncoder::new()
+            .encode(&mut &data[..], &mut stream)
+            .unwrap();
+        stream.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let mut decoded = Vec::new();
+        let err = LzDecoder::new()
+            .decode(&mut &stream[..], &mut decoded)
+            .unwrap_err();
+
+        assert!(matches!(err, DecodeError::UnknownDataAtEnd));
+    }
+
+    #[test]
+    fn test_decode_trailing_garbage_after_skippable_frame_is_unknown_data_at_end() {
+        let mut stream = Vec::new();
+        stream.extend_from_slice(&0x184D2A50u32.to_le_bytes());
+        stream.extend_from_slice(&4u32.to_le_bytes());
+        stream.extend_from_slice(b"skip");
+        stream.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let mut decoded = Vec::new();
+        let err = LzDecoder::new()
+            .decode(&mut &stream[..], &mut decoded)
+            .unwrap_err();
+
+        assert!(matches!(err, DecodeError::UnknownDataAtEnd));
+    }
+
+    #[test]
+    fn test_decode_reader_trailing_garbage_is_unknown_data_at_end() {
+        let mut stream = Vec::new();
+        stream.extend_from_slice(&0x184D2A50u32.to_le_bytes());
+        stream.extend_from_slice(&4u32.to_le_bytes());
+        stream.extend_from_slice(b"skip");
+        stream.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let mut reader = LzDecodeReader::new(&stream[..]);
+        let err = loop {
+            match reader.step() {
+                Ok(()) => {
+                    if matches!(reader.state, ReaderState::Eof) {
+                        panic!("expected trailing garbage to surface an error");
+                    }
+                }
+                Err(e) => break e,
+            }
+        };
+
+        assert!(matches!(err, DecodeError::UnknownDataAtEnd));
+    }
+
+    fn frame_with_content_size(content_size: u64) -> Vec<u8> {
+        // one LZ4-compressed block: a single literals-only sequence closing the block
+        let block: Vec<u8> = vec![0x50, b'H', b'e', b'l', b'l', b'o'];
+
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&LzDecoder::FRAME_MAGIC.to_le_bytes());
+        frame.extend_from_slice(&[0x68, 0x40]); // FLG (block indep + content size), BD
+        frame.extend_from_slice(&content_size.to_le_bytes());
+        frame.push(0); // HC
+        frame.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&block);
+        frame.extend_from_slice(&0u32.to_le_bytes()); // end mark
+        frame
+    }
+
+    #[test]
+    fn test_decode_content_size_ok() {
+        let frame = frame_with_content_size(5);
+
+        let mut decoded = Vec::new();
+        LzDecoder::new()
+            .decode(&mut &frame[..], &mut decoded)
+            .unwrap();
+
+        assert_eq!(decoded, b"Hello");
+    }
+
+    #[test]
+    fn test_decode_content_size_mismatch() {
+        let frame = frame_with_content_size(999);
+
+        let mut decoded = Vec::new();
+        let err = LzDecoder::new()
+            .decode(&mut &frame[..], &mut decoded)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            DecodeError::ContentSizeMismatch {
+                expected: 999,
+                actual: 5
+            }
+        ));
     }
 }
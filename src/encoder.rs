@@ -0,0 +1,284 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+use core::result::Result;
+
+use crate::io_nostd::{self as io, Read, Write};
+use crate::xxhash::xxh32;
+
+#[derive(Debug)]
+pub enum EncodeError {
+    /// Error occured while data reading
+    ReadIoError(io::Error),
+    /// Error occured while data writing
+    WriteIoError(io::Error),
+}
+
+use EncodeError::*;
+
+type EncodeResult<T> = Result<T, EncodeError>;
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Encode Error")
+    }
+}
+
+impl Error for EncodeError {}
+
+/// Encoder for LZ4 compressed data
+#[derive(Debug)]
+pub struct LzEncoder {
+    hash_table: Vec<u32>,
+    block_buf: Vec<u8>,
+}
+
+impl Default for LzEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LzEncoder {
+    const FRAME_MAGIC: u32 = 0x184D2204;
+
+    // Matches `LzDecoder::WINDOW_SIZE`, so offsets produced here always fit
+    // in the decoder's ring buffer.
+    const BLOCK_MAX_SIZE: usize = 1 << 16;
+    const BLOCK_MAX_SIZE_ID: u8 = 4;
+
+    const MIN_MATCH: usize = 4;
+    const MF_LIMIT: usize = 12;
+    const LAST_LITERALS: usize = 5;
+    const MAX_OFFSET: usize = 0xFFFF;
+
+    const HASH_TABLE_BITS: u32 = 16;
+    const HASH_TABLE_SIZE: usize = 1 << Self::HASH_TABLE_BITS;
+    const HASH_MULTIPLIER: u32 = 2654435761;
+    const NO_POS: u32 = u32::MAX;
+
+    /// Create new encoder
+    pub fn new() -> Self {
+        LzEncoder {
+            hash_table: vec![Self::NO_POS; Self::HASH_TABLE_SIZE],
+            block_buf: vec![0u8; Self::BLOCK_MAX_SIZE],
+        }
+    }
+
+    /// Read raw data from `input`, compress it and write an LZ4 frame to `output`
+    pub fn encode<R, W>(&mut self, input: &mut R, output: &mut W) -> EncodeResult<()>
+    where
+        R: Read,
+        W: Write,
+    {
+        self.write_header(output)?;
+
+        loop {
+            let n = Self::fill_block(input, &mut self.block_buf)?;
+            if n == 0 {
+                break;
+            }
+            Self::write_block(&mut self.hash_table, output, &self.block_buf[..n])?;
+        }
+
+        output
+            .write_all(&0u32.to_le_bytes())
+            .map_err(WriteIoError)?;
+        Ok(())
+    }
+
+    fn write_header<W: Write>(&self, output: &mut W) -> EncodeResult<()> {
+        output
+            .write_all(&Self::FRAME_MAGIC.to_le_bytes())
+            .map_err(WriteIoError)?;
+
+        /*
+        |  BitNb  |  7-6  |   5   |    4     |  3   |    2     |    1     |   0  |
+        | ------- |-------|-------|----------|------|----------|----------|------|
+        |FieldName|Version|B.Indep|B.Checksum|C.Size|C.Checksum|*Reserved*|DictID|
+        */
+        let flg_byte: u8 = 0b01 << 6 | 1 << 5;
+        let bd_byte: u8 = Self::BLOCK_MAX_SIZE_ID << 4;
+
+        let hc_byte = (xxh32(&[flg_byte, bd_byte], 0) >> 8) as u8;
+
+        output
+            .write_all(&[flg_byte, bd_byte, hc_byte])
+            .map_err(WriteIoError)
+    }
+
+    fn fill_block<R: Read>(input: &mut R, buf: &mut [u8]) -> EncodeResult<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            let n = input.read(&mut buf[filled..]).map_err(ReadIoError)?;
+            if n == 0 {
+                break;
+            }
+            filled += n;
+        }
+        Ok(filled)
+    }
+
+    fn write_block<W: Write>(
+        hash_table: &mut [u32],
+        output: &mut W,
+        block: &[u8],
+    ) -> EncodeResult<()> {
+        let compressed = Self::compress_block(hash_table, block);
+
+        if compressed.len() < block.len() {
+            let bs_data = compressed.len() as u32;
+            output
+                .write_all(&bs_data.to_le_bytes())
+                .map_err(WriteIoError)?;
+            output.write_all(&compressed).map_err(WriteIoError)
+        } else {
+            let bs_data = (block.len() as u32) | (1 << 31);
+            output
+                .write_all(&bs_data.to_le_bytes())
+                .map_err(WriteIoError)?;
+            output.write_all(block).map_err(WriteIoError)
+        }
+    }
+
+    fn compress_block(hash_table: &mut [u32], input: &[u8]) -> Vec<u8> {
+        for slot in hash_table.iter_mut() {
+            *slot = Self::NO_POS;
+        }
+
+        let mut output = Vec::with_capacity(input.len());
+        let input_len = input.len();
+        let mut anchor = 0usize;
+        let mut i = 0usize;
+
+        if input_len > Self::MF_LIMIT {
+            let match_find_limit = input_len - Self::MF_LIMIT;
+            let match_limit = input_len - Self::LAST_LITERALS;
+
+            while i < match_find_limit {
+                let h = Self::hash_position(input, i);
+                let candidate = hash_table[h];
+                hash_table[h] = i as u32;
+
+                if candidate != Self::NO_POS {
+                    let cand = candidate as usize;
+                    let offset = i - cand;
+                    if offset <= Self::MAX_OFFSET && input[cand..cand + 4] == input[i..i + 4] {
+                        let match_len = Self::extend_match(input, cand + 4, i + 4, match_limit);
+                        Self::emit_sequence(&mut output, &input[anchor..i], offset, match_len);
+                        i += match_len;
+                        anchor = i;
+                        continue;
+                    }
+                }
+                i += 1;
+            }
+        }
+
+        Self::emit_last_literals(&mut output, &input[anchor..]);
+        output
+    }
+
+    #[inline]
+    fn hash_position(input: &[u8], pos: usize) -> usize {
+        let v = u32::from_le_bytes([input[pos], input[pos + 1], input[pos + 2], input[pos + 3]]);
+        (v.wrapping_mul(Self::HASH_MULTIPLIER) >> (32 - Self::HASH_TABLE_BITS)) as usize
+    }
+
+    fn extend_match(input: &[u8], mut cand: usize, mut cur: usize, limit: usize) -> usize {
+        let mut match_len = Self::MIN_MATCH;
+        while cur < limit && input[cand] == input[cur] {
+            cand += 1;
+            cur += 1;
+            match_len += 1;
+        }
+        match_len
+    }
+
+    fn write_lsic(output: &mut Vec<u8>, mut value: usize) {
+        while value >= 255 {
+            output.push(255);
+            value -= 255;
+        }
+        output.push(value as u8);
+    }
+
+    fn emit_sequence(output: &mut Vec<u8>, literals: &[u8], offset: usize, match_len: usize) {
+        let lit_len = literals.len();
+        let extra_match_len = match_len - Self::MIN_MATCH;
+
+        let token = ((lit_len.min(15) as u8) << 4) | extra_match_len.min(15) as u8;
+        output.push(token);
+
+        if lit_len >= 15 {
+            Self::write_lsic(output, lit_len - 15);
+        }
+        output.extend_from_slice(literals);
+
+        output.extend_from_slice(&(offset as u16).to_le_bytes());
+
+        if extra_match_len >= 15 {
+            Self::write_lsic(output, extra_match_len - 15);
+        }
+    }
+
+    fn emit_last_literals(output: &mut Vec<u8>, literals: &[u8]) {
+        let lit_len = literals.len();
+        let token = (lit_len.min(15) as u8) << 4;
+        output.push(token);
+
+        if lit_len >= 15 {
+            Self::write_lsic(output, lit_len - 15);
+        }
+        output.extend_from_slice(literals);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::decoder::LzDecoder;
+
+    fn round_trip(data: &[u8]) {
+        let mut encoded = Vec::new();
+        LzEncoder::new()
+            .encode(&mut &data[..], &mut encoded)
+            .unwrap();
+
+        let mut decoded = Vec::new();
+        LzDecoder::new()
+            .decode(&mut &encoded[..], &mut decoded)
+            .unwrap();
+
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_round_trip_empty() {
+        round_trip(&[]);
+    }
+
+    #[test]
+    fn test_round_trip_small() {
+        round_trip(b"hello hello hello hello!");
+    }
+
+    #[test]
+    fn test_round_trip_no_matches() {
+        let data: Vec<u8> = (0..251).map(|x| (x % 251) as u8).collect();
+        round_trip(&data);
+    }
+
+    #[test]
+    fn test_round_trip_repetitive() {
+        let data: Vec<u8> = (0..100_000).map(|_| b'a').collect();
+        round_trip(&data);
+    }
+
+    #[test]
+    fn test_round_trip_multi_block() {
+        let data: Vec<u8> = (0..300_000).map(|x| ((x * 7) % 256) as u8).collect();
+        round_trip(&data);
+    }
+}
@@ -0,0 +1,174 @@
+//! Minimal `Read`/`Write` surface used when the `std` feature is disabled,
+//! covering just the subset of `std::io` the rest of this crate relies on
+//! so the codec can run on `no_std` + `alloc` targets.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Result, Write};
+
+#[cfg(not(feature = "std"))]
+pub use self::nostd::{Error, ErrorKind, Read, Result, Write};
+
+#[cfg(not(feature = "std"))]
+mod nostd {
+    use alloc::vec::Vec;
+    use core::{cmp, fmt, mem};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ErrorKind {
+        UnexpectedEof,
+        WriteZero,
+        InvalidData,
+    }
+
+    /// Stand-in for `std::io::Error`, dropping the `std`-only boxed source
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+    }
+
+    impl Error {
+        pub fn new<E>(kind: ErrorKind, _error: E) -> Self {
+            Error { kind }
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self.kind)
+        }
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Stand-in for `std::io::Read`
+    pub trait Read {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.read(buf)? {
+                    0 => break,
+                    n => buf = &mut mem::take(&mut buf)[n..],
+                }
+            }
+            if buf.is_empty() {
+                Ok(())
+            } else {
+                Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "failed to fill whole buffer",
+                ))
+            }
+        }
+    }
+
+    /// Stand-in for `std::io::Write`
+    pub trait Write {
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+        fn flush(&mut self) -> Result<()>;
+
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf)? {
+                    0 => {
+                        return Err(Error::new(
+                            ErrorKind::WriteZero,
+                            "failed to write whole buffer",
+                        ))
+                    }
+                    n => buf = &buf[n..],
+                }
+            }
+            Ok(())
+        }
+    }
+
+    // Re-implements the `std::io` blanket impls so existing callers (tests
+    // included) that decode from `&[u8]` or encode into `&mut [u8]`/`Vec<u8>`
+    // keep working unchanged.
+
+    impl Read for &[u8] {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let n = cmp::min(buf.len(), self.len());
+            buf[..n].copy_from_slice(&self[..n]);
+            *self = &self[n..];
+            Ok(n)
+        }
+    }
+
+    impl Write for &mut [u8] {
+        fn write(&mut self, data: &[u8]) -> Result<usize> {
+            let n = cmp::min(data.len(), self.len());
+            let (head, tail) = mem::take(self).split_at_mut(n);
+            head.copy_from_slice(&data[..n]);
+            *self = tail;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write(&mut self, data: &[u8]) -> Result<usize> {
+            self.extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write(&mut self, data: &[u8]) -> Result<usize> {
+            (**self).write(data)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_read_exact_from_slice() {
+            let mut src: &[u8] = &[1, 2, 3, 4];
+            let mut buf = [0u8; 4];
+            src.read_exact(&mut buf).unwrap();
+            assert_eq!(buf, [1, 2, 3, 4]);
+            assert!(src.is_empty());
+        }
+
+        #[test]
+        fn test_read_exact_unexpected_eof() {
+            let mut src: &[u8] = &[1, 2];
+            let mut buf = [0u8; 4];
+            let err = src.read_exact(&mut buf).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        }
+
+        #[test]
+        fn test_write_all_to_vec() {
+            let mut dst: Vec<u8> = Vec::new();
+            dst.write_all(&[1, 2, 3]).unwrap();
+            assert_eq!(dst, [1, 2, 3]);
+        }
+
+        #[test]
+        fn test_write_all_to_mut_slice_write_zero() {
+            let mut backing = [0u8; 2];
+            let mut dst: &mut [u8] = &mut backing;
+            let err = dst.write_all(&[1, 2, 3]).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::WriteZero);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! LZ4 block/frame codec.
+//!
+//! Builds on `alloc` alone when the `std` feature is disabled, so it runs
+//! on embedded/no_std targets; enable `std` (default) for `std::io`-backed
+//! `Read`/`Write` and the bundled CLI.
+
+extern crate alloc;
+
+mod buffer;
+pub mod decoder;
+pub mod encoder;
+pub mod io_nostd;
+pub mod xxhash;
+
+pub use decoder::{DecodeError, LzDecodeReader, LzDecoder};
+pub use encoder::{EncodeError, LzEncoder};
@@ -1,9 +1,6 @@
 use std::io::{self, Write};
 
-mod buffer;
-mod decoder;
-
-use decoder::{DecodeError, LzDecoder};
+use rust_lz4::{DecodeError, LzDecoder};
 
 pub fn main() -> io::Result<()> {
     let mut dec = LzDecoder::new();
@@ -0,0 +1,166 @@
+//! XXH32 non-cryptographic checksum, used for LZ4 content and block checksums
+
+const P1: u32 = 2654435761;
+const P2: u32 = 2246822519;
+const P3: u32 = 3266489917;
+const P4: u32 = 668265263;
+const P5: u32 = 374761393;
+
+/// Incremental XXH32 hasher
+#[derive(Debug, Clone)]
+pub struct Xxh32 {
+    seed: u32,
+    total_len: u64,
+    v1: u32,
+    v2: u32,
+    v3: u32,
+    v4: u32,
+    mem: [u8; 16],
+    mem_size: usize,
+}
+
+#[inline]
+fn round(acc: u32, lane: u32) -> u32 {
+    acc.wrapping_add(lane.wrapping_mul(P2))
+        .rotate_left(13)
+        .wrapping_mul(P1)
+}
+
+#[inline]
+fn read_u32_le(data: &[u8]) -> u32 {
+    u32::from_le_bytes([data[0], data[1], data[2], data[3]])
+}
+
+impl Xxh32 {
+    /// Create new hasher with given seed
+    pub fn new(seed: u32) -> Self {
+        Xxh32 {
+            seed,
+            total_len: 0,
+            v1: seed.wrapping_add(P1).wrapping_add(P2),
+            v2: seed.wrapping_add(P2),
+            v3: seed,
+            v4: seed.wrapping_sub(P1),
+            mem: [0u8; 16],
+            mem_size: 0,
+        }
+    }
+
+    /// Feed more data into the running hash
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+
+        if self.mem_size + data.len() < 16 {
+            self.mem[self.mem_size..self.mem_size + data.len()].copy_from_slice(data);
+            self.mem_size += data.len();
+            return;
+        }
+
+        if self.mem_size > 0 {
+            let fill = 16 - self.mem_size;
+            self.mem[self.mem_size..16].copy_from_slice(&data[..fill]);
+
+            self.v1 = round(self.v1, read_u32_le(&self.mem[0..4]));
+            self.v2 = round(self.v2, read_u32_le(&self.mem[4..8]));
+            self.v3 = round(self.v3, read_u32_le(&self.mem[8..12]));
+            self.v4 = round(self.v4, read_u32_le(&self.mem[12..16]));
+
+            data = &data[fill..];
+            self.mem_size = 0;
+        }
+
+        while data.len() >= 16 {
+            self.v1 = round(self.v1, read_u32_le(&data[0..4]));
+            self.v2 = round(self.v2, read_u32_le(&data[4..8]));
+            self.v3 = round(self.v3, read_u32_le(&data[8..12]));
+            self.v4 = round(self.v4, read_u32_le(&data[12..16]));
+            data = &data[16..];
+        }
+
+        if !data.is_empty() {
+            self.mem[..data.len()].copy_from_slice(data);
+            self.mem_size = data.len();
+        }
+    }
+
+    /// Finalize and return the checksum of all data fed so far
+    pub fn digest(&self) -> u32 {
+        let mut acc = if self.total_len >= 16 {
+            self.v1
+                .rotate_left(1)
+                .wrapping_add(self.v2.rotate_left(7))
+                .wrapping_add(self.v3.rotate_left(12))
+                .wrapping_add(self.v4.rotate_left(18))
+        } else {
+            self.seed.wrapping_add(P5)
+        };
+
+        acc = acc.wrapping_add(self.total_len as u32);
+
+        let mut data = &self.mem[..self.mem_size];
+        while data.len() >= 4 {
+            acc = round_tail_word(acc, read_u32_le(&data[0..4]));
+            data = &data[4..];
+        }
+        for &byte in data {
+            acc = round_tail_byte(acc, byte);
+        }
+
+        acc ^= acc >> 15;
+        acc = acc.wrapping_mul(P2);
+        acc ^= acc >> 13;
+        acc = acc.wrapping_mul(P3);
+        acc ^= acc >> 16;
+        acc
+    }
+}
+
+#[inline]
+fn round_tail_word(acc: u32, word: u32) -> u32 {
+    acc.wrapping_add(word.wrapping_mul(P3))
+        .rotate_left(17)
+        .wrapping_mul(P4)
+}
+
+#[inline]
+fn round_tail_byte(acc: u32, byte: u8) -> u32 {
+    acc.wrapping_add((byte as u32).wrapping_mul(P5))
+        .rotate_left(11)
+        .wrapping_mul(P1)
+}
+
+/// Compute the XXH32 checksum of `data` in one call
+pub fn xxh32(data: &[u8], seed: u32) -> u32 {
+    let mut hasher = Xxh32::new(seed);
+    hasher.update(data);
+    hasher.digest()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xxh32_empty() {
+        assert_eq!(xxh32(&[], 0), 0x02cc5d05);
+    }
+
+    #[test]
+    fn test_xxh32_short() {
+        assert_eq!(xxh32(b"abc", 0), 0x32d153ff);
+    }
+
+    #[test]
+    fn test_xxh32_long() {
+        let data: Vec<u8> = (0..1000).map(|x| (x % 251) as u8).collect();
+        let mut hasher = Xxh32::new(0);
+        hasher.update(&data[..17]);
+        hasher.update(&data[17..]);
+        assert_eq!(hasher.digest(), xxh32(&data, 0));
+    }
+
+    #[test]
+    fn test_xxh32_seed() {
+        assert_ne!(xxh32(b"abc", 0), xxh32(b"abc", 1));
+    }
+}